@@ -0,0 +1,257 @@
+//! Optional git awareness layered over directory reads, enabled by
+//! the `git` feature.  Like the rest of this crate, anything that
+//! can't be determined (no repository, a bare repository, a libgit2
+//! failure) degrades to [`GitStatus::Unknown`] rather than an error.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The index-vs-workdir status of a single directory entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitStatus {
+    New,
+    Modified,
+    Deleted,
+    Ignored,
+    Clean,
+    Unknown,
+}
+impl GitStatus {
+    fn from_flags(flags: git2::Status) -> GitStatus {
+        if flags.is_ignored() {
+            GitStatus::Ignored
+        } else if flags.intersects(git2::Status::WT_DELETED | git2::Status::INDEX_DELETED) {
+            GitStatus::Deleted
+        } else if flags.intersects(git2::Status::WT_NEW | git2::Status::INDEX_NEW) {
+            GitStatus::New
+        } else if flags.intersects(
+            git2::Status::WT_MODIFIED
+                | git2::Status::INDEX_MODIFIED
+                | git2::Status::WT_RENAMED
+                | git2::Status::INDEX_RENAMED
+                | git2::Status::WT_TYPECHANGE
+                | git2::Status::INDEX_TYPECHANGE,
+        ) {
+            GitStatus::Modified
+        } else {
+            GitStatus::Clean
+        }
+    }
+}
+
+/// Memoizes repository discovery, keyed by repository root, so that
+/// listing several sibling directories in the same repository only
+/// discovers that repository once (each [`read_dir_with_git`] call
+/// still opens it and scans its status afresh).  Directories that turn
+/// out not to be inside any repository are memoized too, so repeated
+/// queries against plain, non-repository directories don't re-walk
+/// the filesystem looking for one.
+pub struct GitCache {
+    roots: Mutex<HashSet<PathBuf>>,
+    misses: Mutex<HashSet<PathBuf>>,
+}
+impl GitCache {
+    /// Create an empty cache.
+    pub fn new() -> GitCache {
+        GitCache {
+            roots: Mutex::new(HashSet::new()),
+            misses: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Is there a `.git` entry anywhere between `dir` and `root`
+    /// (inclusive of `dir`, exclusive of `root`)?  If so, `dir`
+    /// is inside a repository nested within `root` (e.g. a git
+    /// submodule), and `root` is the wrong answer for it.
+    fn nested_repo_between(dir: &Path, root: &Path) -> bool {
+        let mut cur = dir;
+        while cur != root {
+            if cur.join(".git").exists() {
+                return true;
+            }
+            match cur.parent() {
+                Some(parent) => cur = parent,
+                None => return false,
+            }
+        }
+        false
+    }
+
+    fn workdir_for(&self, dir: &Path) -> Option<PathBuf> {
+        let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        if self.misses.lock().unwrap().contains(&dir) {
+            return None;
+        }
+        {
+            let roots = self.roots.lock().unwrap();
+            let mut candidates: Vec<_> = roots
+                .iter()
+                .filter(|root| dir.starts_with(root.as_path()))
+                .filter(|root| !Self::nested_repo_between(&dir, root))
+                .collect();
+            candidates.sort_by_key(|root| root.as_os_str().len());
+            if let Some(root) = candidates.last() {
+                return Some((*root).clone());
+            }
+        }
+        match git2::Repository::discover(&dir)
+            .ok()
+            .and_then(|repo| repo.workdir().map(|w| w.to_path_buf()))
+        {
+            Some(root) => {
+                let root = std::fs::canonicalize(&root).unwrap_or(root);
+                self.roots.lock().unwrap().insert(root.clone());
+                Some(root)
+            }
+            None => {
+                self.misses.lock().unwrap().insert(dir);
+                None
+            }
+        }
+    }
+}
+impl Default for GitCache {
+    fn default() -> GitCache {
+        GitCache::new()
+    }
+}
+
+/// Everything `read_dir_with_git` needs to know about a repository's
+/// tracked files: the non-clean ones (from `git status`), and the
+/// full set of tracked paths (from the index) so that a tracked path
+/// absent from `statuses` can be told apart from one that's merely
+/// outside the repository altogether.
+#[derive(Default)]
+struct RepoState {
+    statuses: HashMap<PathBuf, GitStatus>,
+    tracked: HashSet<PathBuf>,
+}
+
+fn repo_state(workdir: &Path) -> RepoState {
+    let repo = match git2::Repository::open(workdir) {
+        Ok(repo) => repo,
+        Err(_) => return RepoState::default(),
+    };
+    let mut statuses = HashMap::new();
+    let mut opts = git2::StatusOptions::new();
+    opts.show(git2::StatusShow::IndexAndWorkdir)
+        .include_untracked(true)
+        .include_ignored(true)
+        .recurse_untracked_dirs(false);
+    if let Ok(entries) = repo.statuses(Some(&mut opts)) {
+        for entry in entries.iter() {
+            if let Some(path) = entry.path() {
+                statuses.insert(workdir.join(path), GitStatus::from_flags(entry.status()));
+            }
+        }
+    }
+    let mut tracked = HashSet::new();
+    if let Ok(index) = repo.index() {
+        for entry in index.iter() {
+            if let Ok(path) = std::str::from_utf8(&entry.path) {
+                tracked.insert(workdir.join(path));
+            }
+        }
+    }
+    RepoState { statuses, tracked }
+}
+
+/// The iterator over `(DirEntry, GitStatus)` pairs produced by
+/// [`read_dir_with_git`].
+pub struct ReadDirWithGit {
+    inner: crate::ReadDir,
+    // The canonical form of the queried directory, i.e. the parent of
+    // every entry `inner` yields, computed once up front so looking
+    // up each entry's status doesn't re-canonicalize the same
+    // directory on every call to `next`.
+    base: Option<PathBuf>,
+    state: RepoState,
+}
+impl Iterator for ReadDirWithGit {
+    type Item = (std::fs::DirEntry, GitStatus);
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.inner.next()?;
+        // `state` is keyed by the canonical, absolute path of each
+        // entry, but `entry.path()` stays relative whenever
+        // `read_dir_with_git` was called with a relative path, so
+        // look entries up by `base` joined with their name.
+        let key = self
+            .base
+            .as_ref()
+            .map(|base| base.join(entry.file_name()))
+            .unwrap_or_else(|| entry.path());
+        // `git status` never lists unmodified tracked files, so a
+        // tracked path absent from `statuses` is clean; anything else
+        // absent (an untouched directory, or no repository at all)
+        // is genuinely indeterminate.
+        let status = self.state.statuses.get(&key).copied().unwrap_or_else(|| {
+            if self.state.tracked.contains(&key) {
+                GitStatus::Clean
+            } else {
+                GitStatus::Unknown
+            }
+        });
+        Some((entry, status))
+    }
+}
+
+/// Read a directory exactly as [`crate::read_dir`] does, but pair
+/// each entry with its [`GitStatus`].  Any failure to find or open
+/// the containing repository yields `GitStatus::Unknown` for every
+/// entry, so this always returns every readable entry just as
+/// `read_dir` does today.
+pub fn read_dir_with_git<P: AsRef<Path>>(path: P, cache: &GitCache) -> ReadDirWithGit {
+    let path = path.as_ref();
+    let state = cache.workdir_for(path).map(|w| repo_state(&w)).unwrap_or_default();
+    ReadDirWithGit {
+        inner: crate::read_dir(path),
+        base: std::fs::canonicalize(path).ok(),
+        state,
+    }
+}
+
+#[test]
+fn read_dir_with_git_reports_status_for_relative_path() {
+    // A throwaway repository under `target/`, queried by a relative
+    // path, to guard against statuses being keyed by an absolute path
+    // that never matches a relative query.
+    let dir = PathBuf::from(format!("target/lazyfs-git-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let repo = git2::Repository::init(&dir).unwrap();
+    std::fs::write(dir.join("tracked.txt"), "one").unwrap();
+    std::fs::write(dir.join("clean.txt"), "unchanged").unwrap();
+    {
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("tracked.txt")).unwrap();
+        index.add_path(Path::new("clean.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        index.write().unwrap();
+        let sig = git2::Signature::now("lazyfs tests", "lazyfs@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+    }
+    std::fs::write(dir.join("tracked.txt"), "two").unwrap();
+    std::fs::write(dir.join("untracked.txt"), "new").unwrap();
+
+    let cache = GitCache::new();
+    let statuses: HashMap<_, _> = read_dir_with_git(&dir, &cache)
+        .map(|(entry, status)| (entry.file_name(), status))
+        .collect();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        statuses.get(std::ffi::OsStr::new("tracked.txt")),
+        Some(&GitStatus::Modified)
+    );
+    assert_eq!(
+        statuses.get(std::ffi::OsStr::new("untracked.txt")),
+        Some(&GitStatus::New)
+    );
+    assert_eq!(
+        statuses.get(std::ffi::OsStr::new("clean.txt")),
+        Some(&GitStatus::Clean)
+    );
+}
@@ -13,6 +13,9 @@
 //! }
 //! ```
 
+#[cfg(feature = "git")]
+pub mod git;
+
 /// The iterator over entries in a directory.  It returns all the
 /// entries that can be read without error.
 pub enum ReadDir {
@@ -46,6 +49,415 @@ pub fn read_dir<P: AsRef<std::path::Path>>(path: P) -> ReadDir {
     }
 }
 
+/// What to order [`SortedDirEntries`] by.
+enum SortKey {
+    Name,
+    Modified,
+    Size,
+}
+
+/// Which entries [`SortedDirEntries`] should keep.
+enum EntryKind {
+    Any,
+    FilesOnly,
+    DirsOnly,
+}
+
+/// A builder that collects a directory's entries, filters them by
+/// type, and sorts them, all in one call.  Like the rest of this
+/// crate, entries whose metadata can't be stat'd are dropped rather
+/// than causing an error.
+pub struct SortedDirEntries<P: AsRef<std::path::Path>> {
+    path: P,
+    sort_by: SortKey,
+    kind: EntryKind,
+}
+impl<P: AsRef<std::path::Path>> SortedDirEntries<P> {
+    /// Sort by last-modified time instead of by name.
+    pub fn sort_by_modified(mut self) -> Self {
+        self.sort_by = SortKey::Modified;
+        self
+    }
+    /// Sort by size instead of by name.
+    pub fn sort_by_size(mut self) -> Self {
+        self.sort_by = SortKey::Size;
+        self
+    }
+    /// Keep only regular files.
+    pub fn files_only(mut self) -> Self {
+        self.kind = EntryKind::FilesOnly;
+        self
+    }
+    /// Keep only directories.
+    pub fn dirs_only(mut self) -> Self {
+        self.kind = EntryKind::DirsOnly;
+        self
+    }
+    /// Collect the filtered, sorted entries.
+    pub fn read(self) -> Vec<std::fs::DirEntry> {
+        let SortedDirEntries { path, sort_by, kind } = self;
+        let mut entries: Vec<_> = read_dir(path)
+            .filter(|entry| {
+                let meta = match entry.metadata() {
+                    Ok(meta) => meta,
+                    Err(_) => return false,
+                };
+                match kind {
+                    EntryKind::Any => true,
+                    EntryKind::FilesOnly => meta.is_file(),
+                    EntryKind::DirsOnly => meta.is_dir(),
+                }
+            })
+            .collect();
+        match sort_by {
+            SortKey::Name => entries.sort_by_key(|entry| entry.file_name()),
+            SortKey::Modified => {
+                entries.sort_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())
+            }
+            SortKey::Size => {
+                entries.sort_by_key(|entry| entry.metadata().map(|m| m.len()).unwrap_or(0))
+            }
+        }
+        entries
+    }
+}
+
+/// Start building a sorted, filtered read of a directory, e.g.
+/// `sorted_dir_entries(path).dirs_only().sort_by_modified().read()`.
+pub fn sorted_dir_entries<P: AsRef<std::path::Path>>(path: P) -> SortedDirEntries<P> {
+    SortedDirEntries {
+        path,
+        sort_by: SortKey::Name,
+        kind: EntryKind::Any,
+    }
+}
+
+/// Read a directory's entries, sorted by name.  Errors are ignored
+/// just as in [`read_dir`].
+pub fn read_dir_sorted<P: AsRef<std::path::Path>>(path: P) -> Vec<std::fs::DirEntry> {
+    sorted_dir_entries(path).read()
+}
+
+#[test]
+fn read_dir_sorted_is_alphabetical() {
+    let names: Vec<_> = read_dir_sorted("src")
+        .into_iter()
+        .map(|e| e.file_name())
+        .collect();
+    let mut sorted_names = names.clone();
+    sorted_names.sort();
+    assert_eq!(names, sorted_names);
+}
+
+#[test]
+fn sorted_dir_entries_files_only() {
+    let entries = sorted_dir_entries(".").files_only().read();
+    assert!(entries.iter().all(|e| e.metadata().unwrap().is_file()));
+    assert!(entries.iter().any(|e| e.file_name() == ".gitignore"));
+}
+
+#[test]
+fn sorted_dir_entries_dirs_only() {
+    let entries = sorted_dir_entries(".").dirs_only().read();
+    assert!(entries.iter().all(|e| e.metadata().unwrap().is_dir()));
+    assert!(entries.iter().any(|e| e.file_name() == "src"));
+}
+
+#[test]
+fn sorted_dir_entries_by_size() {
+    let entries = sorted_dir_entries("src").files_only().sort_by_size().read();
+    let sizes: Vec<_> = entries.iter().map(|e| e.metadata().unwrap().len()).collect();
+    let mut sorted_sizes = sizes.clone();
+    sorted_sizes.sort();
+    assert_eq!(sizes, sorted_sizes);
+}
+
+/// Read the entire contents of a file.  In case of error, return
+/// `None` rather than a `Result`.
+pub fn read<P: AsRef<std::path::Path>>(path: P) -> Option<Vec<u8>> {
+    std::fs::read(path).ok()
+}
+
+/// Read the entire contents of a file into a `String`.  In case of
+/// error (including invalid UTF-8), return `None`.
+pub fn read_to_string<P: AsRef<std::path::Path>>(path: P) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+/// Query the metadata of a path, following symlinks.  In case of
+/// error, return `None`.
+pub fn metadata<P: AsRef<std::path::Path>>(path: P) -> Option<std::fs::Metadata> {
+    std::fs::metadata(path).ok()
+}
+
+/// Query the metadata of a path, without following symlinks.  In
+/// case of error, return `None`.
+pub fn symlink_metadata<P: AsRef<std::path::Path>>(path: P) -> Option<std::fs::Metadata> {
+    std::fs::symlink_metadata(path).ok()
+}
+
+/// Resolve a path to an absolute, canonical form.  In case of error,
+/// return `None`.
+pub fn canonicalize<P: AsRef<std::path::Path>>(path: P) -> Option<std::path::PathBuf> {
+    std::fs::canonicalize(path).ok()
+}
+
+/// Return whether `path` exists, treating any error (including a
+/// missing path) as `false`.
+pub fn exists<P: AsRef<std::path::Path>>(path: P) -> bool {
+    metadata(path).is_some()
+}
+
+/// Return whether `path` exists and is a directory, treating any
+/// error as `false`.
+pub fn is_dir<P: AsRef<std::path::Path>>(path: P) -> bool {
+    metadata(path).map(|m| m.is_dir()).unwrap_or(false)
+}
+
+/// Return whether `path` exists and is a regular file, treating any
+/// error as `false`.
+pub fn is_file<P: AsRef<std::path::Path>>(path: P) -> bool {
+    metadata(path).map(|m| m.is_file()).unwrap_or(false)
+}
+
+#[test]
+fn read_to_string_self() {
+    let s = read_to_string("src/lib.rs").unwrap();
+    assert!(s.contains("fn read_to_string"));
+}
+
+#[test]
+fn read_nonexistent_file() {
+    assert_eq!(read("this file does not exist"), None);
+    assert_eq!(read_to_string("this file does not exist"), None);
+}
+
+#[test]
+fn metadata_and_predicates() {
+    assert!(is_dir("src"));
+    assert!(is_file("src/lib.rs"));
+    assert!(!is_file("src"));
+    assert!(!is_dir("src/lib.rs"));
+    assert!(exists("src"));
+    assert!(!exists("this does not exist"));
+    assert!(metadata("src").is_some());
+    assert!(symlink_metadata("src").is_some());
+    assert!(canonicalize("src").is_some());
+    assert!(metadata("this does not exist").is_none());
+}
+
+/// A snapshot of a directory's contents, read once and indexed for
+/// fast repeated membership queries.  Like the rest of this crate,
+/// any entry or directory that can't be read is simply left out
+/// rather than causing an error, so an unreadable directory yields an
+/// empty but still queryable `DirContents`.
+pub struct DirContents {
+    file_names: std::collections::HashSet<std::ffi::OsString>,
+    extensions: std::collections::HashSet<std::ffi::OsString>,
+    folder_names: std::collections::HashSet<std::ffi::OsString>,
+}
+impl DirContents {
+    /// Is there a non-directory entry (a regular file, a symlink, etc.)
+    /// named exactly `name` in this directory?
+    pub fn has_file_name(&self, name: &str) -> bool {
+        self.file_names.contains(std::ffi::OsStr::new(name))
+    }
+    /// Is there any non-directory entry whose extension is `extension`?
+    pub fn has_extension(&self, extension: &str) -> bool {
+        self.extensions.contains(std::ffi::OsStr::new(extension))
+    }
+    /// Is there a subdirectory named exactly `name`?
+    pub fn has_folder(&self, name: &str) -> bool {
+        self.folder_names.contains(std::ffi::OsStr::new(name))
+    }
+}
+
+/// Eagerly read a directory once into a [`DirContents`] snapshot that
+/// can answer membership queries in constant time.  Entries that
+/// can't be stat'd, and directories that can't be read at all, are
+/// simply skipped.
+pub fn dir_contents<P: AsRef<std::path::Path>>(path: P) -> DirContents {
+    let mut file_names = std::collections::HashSet::new();
+    let mut extensions = std::collections::HashSet::new();
+    let mut folder_names = std::collections::HashSet::new();
+    for entry in read_dir(path) {
+        let is_dir = match entry.file_type() {
+            Ok(t) => t.is_dir(),
+            Err(_) => continue,
+        };
+        if is_dir {
+            folder_names.insert(entry.file_name());
+        } else {
+            file_names.insert(entry.file_name());
+            if let Some(ext) = entry.path().extension() {
+                extensions.insert(ext.to_os_string());
+            }
+        }
+    }
+    DirContents {
+        file_names,
+        extensions,
+        folder_names,
+    }
+}
+
+#[test]
+fn dir_contents_of_src() {
+    let dc = dir_contents("src");
+    assert!(dc.has_file_name("lib.rs"));
+    assert!(dc.has_extension("rs"));
+    assert!(!dc.has_extension("toml"));
+    assert!(!dc.has_folder("lib.rs"));
+}
+
+#[test]
+fn dir_contents_finds_folders() {
+    let dc = dir_contents(".git");
+    assert!(dc.has_folder("refs"));
+    assert!(dc.has_folder("objects"));
+    assert!(dc.has_file_name("HEAD"));
+}
+
+#[test]
+fn dir_contents_of_nonexistent_is_empty_but_queryable() {
+    let dc = dir_contents("this does not exist");
+    assert!(!dc.has_file_name("anything"));
+    assert!(!dc.has_extension("rs"));
+    assert!(!dc.has_folder("anything"));
+}
+
+type EntryFilter = Box<dyn FnMut(&std::fs::DirEntry) -> bool>;
+
+/// The iterator over entries reachable beneath a directory, recursing
+/// into subdirectories.  Like [`ReadDir`], it silently skips anything
+/// that can't be read rather than aborting the walk.
+pub struct WalkDir {
+    stack: Vec<std::fs::ReadDir>,
+    filter: Option<EntryFilter>,
+    min_depth: usize,
+    max_depth: usize,
+}
+impl WalkDir {
+    /// Only yield entries at least `depth` levels below the root.
+    pub fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+    /// Don't yield or descend into entries more than `depth` levels
+    /// below the root.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+    /// Install a predicate that decides whether to keep an entry, as
+    /// in walkdir's `filter_entry`: an entry the predicate rejects is
+    /// not yielded, and if it's a directory its subtree is pruned
+    /// entirely rather than being descended into.
+    pub fn filter_entry<P>(mut self, predicate: P) -> Self
+    where
+        P: FnMut(&std::fs::DirEntry) -> bool + 'static,
+    {
+        self.filter = Some(Box::new(predicate));
+        self
+    }
+}
+impl Iterator for WalkDir {
+    type Item = std::fs::DirEntry;
+    fn next(&mut self) -> Option<std::fs::DirEntry> {
+        loop {
+            let depth = self.stack.len();
+            let entry = match self.stack.last_mut() {
+                None => return None,
+                Some(rd) => loop {
+                    match rd.next() {
+                        Some(Ok(entry)) => break Some(entry),
+                        Some(Err(_)) => continue,
+                        None => break None,
+                    }
+                },
+            };
+            let entry = match entry {
+                Some(entry) => entry,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            if let Some(ref mut filter) = self.filter {
+                if !filter(&entry) {
+                    continue;
+                }
+            }
+            if depth < self.max_depth {
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                if is_dir {
+                    if let Ok(child) = std::fs::read_dir(entry.path()) {
+                        self.stack.push(child);
+                    }
+                }
+            }
+            if depth >= self.min_depth && depth <= self.max_depth {
+                return Some(entry);
+            }
+        }
+    }
+}
+
+/// Walk a directory tree, yielding every entry reachable beneath
+/// `path` that can be read without error.  Any directory that can't
+/// be opened (permission denied, not a directory, etc.) is silently
+/// skipped rather than aborting the walk.
+pub fn walk_dir<P: AsRef<std::path::Path>>(path: P) -> WalkDir {
+    let stack = match std::fs::read_dir(path) {
+        Ok(rd) => vec![rd],
+        Err(_) => Vec::new(),
+    };
+    WalkDir {
+        stack,
+        filter: None,
+        min_depth: 0,
+        max_depth: usize::MAX,
+    }
+}
+
+#[test]
+fn walk_dotgit() {
+    let git_stuff: Vec<_> = walk_dir(".git")
+        .map(|p| p.path().to_string_lossy().to_string())
+        .collect();
+    println!("{:?}", git_stuff);
+    assert!(git_stuff.contains(&".git/refs".to_string()));
+    assert!(git_stuff.contains(&".git/refs/heads".to_string()));
+    assert!(git_stuff.contains(&".git/HEAD".to_string()));
+}
+
+#[test]
+fn walk_dir_prunes_with_filter_entry() {
+    let names: Vec<_> = walk_dir(".")
+        .filter_entry(|e| e.file_name() != ".git")
+        .map(|p| p.path().to_string_lossy().to_string())
+        .collect();
+    assert!(!names.contains(&"./.git".to_string()));
+    assert!(!names.iter().any(|n| n.starts_with("./.git/")));
+    assert!(names.contains(&"./src".to_string()));
+}
+
+#[test]
+fn walk_dir_max_depth() {
+    let names: Vec<_> = walk_dir(".")
+        .max_depth(1)
+        .map(|p| p.path().to_string_lossy().to_string())
+        .collect();
+    assert!(names.contains(&"./src".to_string()));
+    assert!(!names.iter().any(|n| n == "./src/lib.rs"));
+}
+
+#[test]
+fn walk_nonexistent() {
+    let stuff: Vec<_> = walk_dir("this does not exist").collect();
+    assert_eq!(stuff.len(), 0);
+}
+
 #[test]
 fn read_dotgit() {
     let git_stuff: Vec<_> = read_dir(".git").map(|p| {
@@ -65,12 +477,12 @@ fn read_dotgit() {
 #[test]
 fn read_src() {
     let stuff: Vec<_> = read_dir("src").filter(|p| {
-        p.path().extension() == Some(&std::ffi::OsStr::new("rs"))
+        p.path().extension() == Some(std::ffi::OsStr::new("rs"))
     }).map(|p| {
         p.path().to_string_lossy().to_string()
     }).collect();
     println!("{:?}", stuff);
-    assert_eq!(stuff.len(), 1);
+    assert_eq!(stuff.len(), 2);
 }
 
 #[test]